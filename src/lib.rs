@@ -1,43 +1,316 @@
-use chrono::Local;
+use chrono::{Local, SecondsFormat};
 use core::fmt;
 use lazy_static::lazy_static;
-use std::{fs::OpenOptions, io::Write, sync::Mutex};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Sender, SyncSender};
+use std::thread::JoinHandle;
+use std::{fs::OpenOptions, io::IsTerminal, io::Write, sync::Mutex};
 
 lazy_static! {
     static ref LOG_LEVEL: Mutex<LogLevel> = Mutex::new(LogLevel::Default);
     static ref LOG_PATH: Mutex<String> = Mutex::new(String::new());
+    static ref LOG_FORMAT: Mutex<LogFormat> = Mutex::new(LogFormat::Text);
+    /// Per-target level overrides, as `(prefix, level)` pairs. Consulted with
+    /// longest-prefix-wins precedence ahead of the global `LOG_LEVEL`.
+    static ref FILTERS: Mutex<Vec<(String, LogLevel)>> = Mutex::new(Vec::new());
+    static ref COLOR_MODE: Mutex<ColorMode> = Mutex::new(ColorMode::Auto);
+    static ref ROTATION: Mutex<RotationPolicy> = Mutex::new(RotationPolicy::None);
+    /// Maximum number of rotated archives to keep per path, `None` for no limit.
+    static ref MAX_ARCHIVES: Mutex<Option<usize>> = Mutex::new(None);
+    /// The local date last written to each active path, for `Daily` rotation.
+    static ref ROTATION_DATE: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+    /// Serializes the rotate-open-write sequence so concurrent loggers writing
+    /// to the same path cannot race on rotation.
+    static ref FILE_IO: Mutex<()> = Mutex::new(());
+    /// Sender into the background writer thread when async mode is active.
+    static ref ASYNC_TX: Mutex<Option<SyncSender<LogMessage>>> = Mutex::new(None);
+    /// Join handle for the background writer thread, used by `shutdown`.
+    static ref ASYNC_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
 }
 
-/// `Verbose`    -> Logs all messages, regardless of `LogType`.  
-/// `Debug`      -> Logs messages marked as `LogType::Error`, `LogType::Warning` and
-///                 `LogType::Debug`.  
-/// `Default`    -> Logs messages marked as `LogType::Error` and `LogType::Warning`.  
-/// `ErrorsOnly` -> Only logs messages marked as `LogType::Error`.
+/// Count of records dropped because the bounded async channel was full. It is
+/// flushed as a diagnostic by the writer thread on its next record.
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// `Verbose`    -> Logs all messages, regardless of `LogType` (including `Trace`).
+/// `Debug`      -> Logs everything except `LogType::Trace` and `LogType::Info`.
+/// `Info`       -> Logs `LogType::Info` and everything more severe.
+/// `Default`    -> Logs `LogType::Warning` and everything more severe.
+/// `ErrorsOnly` -> Logs `LogType::Error` and `LogType::Critical`.
+/// `Critical`   -> Only logs messages marked as `LogType::Critical`.
 #[derive(Debug, PartialEq, Clone)]
 pub enum LogLevel {
     Verbose,
     Debug,
+    Info,
     Default,
     ErrorsOnly,
+    Critical,
 }
 
 #[derive(Debug, PartialEq)]
 pub enum LogType {
-    Info,
+    Trace,
     Debug,
+    Info,
     Warning,
     Error,
+    Critical,
 }
 
 impl fmt::Display for LogType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Info => write!(f, "INFO"),
+            Self::Trace => write!(f, "TRACE"),
             Self::Debug => write!(f, "DEBUG"),
+            Self::Info => write!(f, "INFO"),
             Self::Warning => write!(f, "WARNING"),
             Self::Error => write!(f, "ERROR"),
+            Self::Critical => write!(f, "CRITICAL"),
+        }
+    }
+}
+
+impl LogType {
+    /// The numeric severity of this type, using the same scale as Bunyan
+    /// (`TRACE` -> 10, `DEBUG` -> 20, `INFO` -> 30, `WARNING` -> 40,
+    /// `ERROR` -> 50, `CRITICAL` -> 60). Used by the `LogFormat::Json` renderer.
+    fn severity(&self) -> u8 {
+        match self {
+            Self::Trace => 10,
+            Self::Debug => 20,
+            Self::Info => 30,
+            Self::Warning => 40,
+            Self::Error => 50,
+            Self::Critical => 60,
+        }
+    }
+}
+
+/// `Text` -> the human-readable `[TYPE] timestamp -> message` line (the default).
+/// `Json` -> a single-line, Bunyan-style JSON object per record, suitable for
+///           ingestion by log processors without regex scraping.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+/// Sets the format used to render each record.
+/// See `LogFormat` for a description of what each format produces.
+pub fn set_format(format: LogFormat) {
+    *LOG_FORMAT.lock().unwrap() = format;
+}
+
+/// `Auto`   -> colorise only when the target stream is a terminal and `NO_COLOR`
+///             is unset (the default).
+/// `Always` -> always colorise the console output.
+/// `Never`  -> never colorise.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Sets whether the `[LEVEL]` signifier on the stdout/stderr path is coloured.
+/// Only console output is affected — records written to the log file are always
+/// plain and escape-free so the file stays parseable.
+pub fn set_color(mode: ColorMode) {
+    *COLOR_MODE.lock().unwrap() = mode;
+}
+
+/// `None`        -> never rotate (the default); the file grows without bound.
+/// `Size(bytes)` -> rotate once the active file exceeds `bytes`.
+/// `Daily`       -> rotate at local-date boundaries.
+#[derive(Debug, PartialEq, Clone)]
+pub enum RotationPolicy {
+    None,
+    Size(u64),
+    Daily,
+}
+
+/// Sets the policy used to rotate the active log file. Rotation is checked
+/// before each write to the file (on the caller's thread, or on the background
+/// writer thread in async mode), while the file lock is held so concurrent
+/// loggers do not race.
+pub fn set_rotation(policy: RotationPolicy) {
+    *ROTATION.lock().unwrap() = policy;
+}
+
+/// Sets how many rotated archives to keep per path; older archives are pruned
+/// after each rotation. `None` (the default) keeps every archive.
+pub fn set_max_archives(max: Option<usize>) {
+    *MAX_ARCHIVES.lock().unwrap() = max;
+}
+
+/// Rotates the file at `path` if the current `RotationPolicy` calls for it,
+/// renaming the active file to an indexed (`Size`) or date-suffixed (`Daily`)
+/// name and letting the subsequent append recreate a fresh file. Surfaces any
+/// `io::Error` from the rename so the caller can propagate it.
+fn maybe_rotate(path: &str) -> Result<(), std::io::Error> {
+    let policy = ROTATION.lock().unwrap().clone();
+    match policy {
+        RotationPolicy::None => {}
+        RotationPolicy::Size(limit) => {
+            if let Ok(metadata) = std::fs::metadata(path) {
+                if metadata.len() > limit {
+                    std::fs::rename(path, next_indexed_name(path))?;
+                    prune_archives(path);
+                }
+            }
+        }
+        RotationPolicy::Daily => {
+            let today = Local::now().format("%Y-%m-%d").to_string();
+            let mut dates = ROTATION_DATE.lock().unwrap();
+            match dates.get(path) {
+                Some(previous) if *previous != today && std::fs::metadata(path).is_ok() => {
+                    std::fs::rename(path, format!("{}.{}", path, previous))?;
+                    prune_archives(path);
+                }
+                _ => {}
+            }
+            dates.insert(path.to_string(), today);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the first unused `path.N` archive name, counting up from 1.
+fn next_indexed_name(path: &str) -> String {
+    let mut index = 1;
+    loop {
+        let candidate = format!("{}.{}", path, index);
+        if !std::path::Path::new(&candidate).exists() {
+            return candidate;
+        }
+        index += 1;
+    }
+}
+
+/// Removes the oldest archives for `path` beyond the configured `MAX_ARCHIVES`.
+fn prune_archives(path: &str) {
+    let max = match *MAX_ARCHIVES.lock().unwrap() {
+        Some(max) => max,
+        None => return,
+    };
+
+    let active = std::path::Path::new(path);
+    let dir = match active.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => std::path::PathBuf::from("."),
+    };
+    let prefix = match active.file_name().and_then(|name| name.to_str()) {
+        Some(name) => format!("{}.", name),
+        None => return,
+    };
+
+    let mut archives: Vec<_> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.starts_with(&prefix))
+            })
+            .collect(),
+        Err(_) => return,
+    };
+
+    if archives.len() <= max {
+        return;
+    }
+
+    archives.sort_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
+    let remove_count = archives.len() - max;
+    for entry in archives.into_iter().take(remove_count) {
+        let _ = std::fs::remove_file(entry.path());
+    }
+}
+
+/// The ANSI SGR colour code for a type's `[LEVEL]` signifier, matching
+/// `env_logger`'s terminal styling (error red, warning yellow, info green,
+/// debug blue).
+fn color_code(t: &LogType) -> &'static str {
+    match t {
+        LogType::Critical => "1;31",
+        LogType::Error => "31",
+        LogType::Warning => "33",
+        LogType::Info => "32",
+        LogType::Debug => "34",
+        LogType::Trace => "36",
+    }
+}
+
+/// Decides whether console output should be coloured for the given stream,
+/// honouring the configured `ColorMode`, terminal detection and `NO_COLOR`.
+fn should_color(is_error: bool) -> bool {
+    match *COLOR_MODE.lock().unwrap() {
+        ColorMode::Never => false,
+        ColorMode::Always => true,
+        ColorMode::Auto => {
+            if std::env::var_os("NO_COLOR").is_some() {
+                return false;
+            }
+            if is_error {
+                std::io::stderr().is_terminal()
+            } else {
+                std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Wraps the leading `[LEVEL]` token of a rendered text line in the ANSI colour
+/// for `t`, leaving the rest of the line untouched.
+fn colorize(line: &str, t: &LogType) -> String {
+    match line.find(']') {
+        Some(end) => format!(
+            "\x1b[{code}m{head}\x1b[0m{rest}",
+            code = color_code(t),
+            head = &line[..=end],
+            rest = &line[end + 1..]
+        ),
+        None => line.to_string(),
+    }
+}
+
+/// Escapes a string for embedding in a JSON string literal.
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
         }
     }
+    escaped
+}
+
+/// Best-effort hostname of the current machine, used for the Bunyan `hostname`
+/// field. Reads the kernel hostname from `/proc`/`/etc` (rather than the
+/// usually-unset `HOSTNAME` shell variable), falling back to `"unknown"` when
+/// it cannot be determined.
+fn hostname() -> String {
+    let from_file = |path: &str| {
+        std::fs::read_to_string(path)
+            .ok()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+    };
+
+    from_file("/proc/sys/kernel/hostname")
+        .or_else(|| from_file("/etc/hostname"))
+        .unwrap_or_else(|| "unknown".to_string())
 }
 
 /// Sets the level of logging.
@@ -46,6 +319,266 @@ pub fn set_level(level: LogLevel) {
     *LOG_LEVEL.lock().unwrap() = level;
 }
 
+/// Logs a `format!`-style message at an explicit `LogType`, under the calling
+/// module's path as the target. The message is only built when the level is
+/// enabled, so suppressed records cost nothing beyond the cheap level check.
+///
+/// ```ignore
+/// sw_log!(LogType::Info, "result: {}", expensive());
+/// ```
+#[macro_export]
+macro_rules! sw_log {
+    ($level:expr, $($arg:tt)+) => {{
+        let t = $level;
+        if $crate::level_enabled_target(&t, module_path!()) {
+            let _ = $crate::log_target(&format!($($arg)+), t, None, &[], module_path!());
+        }
+    }};
+}
+
+/// Logs a `format!`-style message at `LogType::Error`.
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::sw_log!($crate::LogType::Error, $($arg)+) };
+}
+
+/// Logs a `format!`-style message at `LogType::Warning`.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => { $crate::sw_log!($crate::LogType::Warning, $($arg)+) };
+}
+
+/// Logs a `format!`-style message at `LogType::Debug`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::sw_log!($crate::LogType::Debug, $($arg)+) };
+}
+
+/// Logs a `format!`-style message at `LogType::Info`.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::sw_log!($crate::LogType::Info, $($arg)+) };
+}
+
+/// Returns whether the given `LogType` is enabled for the calling module, so
+/// callers can guard expensive computation:
+///
+/// ```ignore
+/// if log_enabled!(LogType::Info) { info!("result: {}", expensive()); }
+/// ```
+#[macro_export]
+macro_rules! log_enabled {
+    ($level:expr) => {
+        $crate::level_enabled_target(&$level, module_path!())
+    };
+}
+
+/// Maps a directive level token (e.g. `warn`, `debug`) to the `LogLevel` whose
+/// threshold lets that level and everything more severe through, except that
+/// `debug` maps to `LogLevel::Debug`, which does not let `LogType::Info` through.
+/// Matching is case-insensitive. Returns `None` for an unrecognised token.
+fn level_from_token(token: &str) -> Option<LogLevel> {
+    match token.trim().to_ascii_lowercase().as_str() {
+        "critical" | "fatal" => Some(LogLevel::Critical),
+        "error" => Some(LogLevel::ErrorsOnly),
+        "warn" | "warning" | "default" => Some(LogLevel::Default),
+        "info" => Some(LogLevel::Info),
+        "debug" => Some(LogLevel::Debug),
+        "trace" | "verbose" => Some(LogLevel::Verbose),
+        _ => None,
+    }
+}
+
+/// Parses an `env_logger`-style directive such as `warn,net=debug,net::http=trace`
+/// into an optional global default level and a table of per-target overrides.
+fn parse_directive(directive: &str) -> (Option<LogLevel>, Vec<(String, LogLevel)>) {
+    let mut global = None;
+    let mut filters = Vec::new();
+
+    for entry in directive.split(',') {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        match entry.split_once('=') {
+            Some((target, level)) => {
+                if let Some(level) = level_from_token(level) {
+                    filters.push((target.trim().to_string(), level));
+                }
+            }
+            None => {
+                if let Some(level) = level_from_token(entry) {
+                    global = Some(level);
+                }
+            }
+        }
+    }
+
+    (global, filters)
+}
+
+/// Where the logger should send records when configured via `ConfigLogging`.
+///
+/// `StderrTerminal` -> log only to the console.
+/// `File`           -> log to the file at `path`, honouring `if_exists`.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfigMode {
+    StderrTerminal,
+    File,
+}
+
+/// What to do when the configured log file already exists.
+///
+/// `Append`   -> append to the existing file (today's behaviour).
+/// `Truncate` -> truncate the existing file on open.
+/// `Fail`     -> refuse to start if the file already exists.
+#[derive(Debug, PartialEq, Clone, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IfExists {
+    #[default]
+    Append,
+    Truncate,
+    Fail,
+}
+
+/// Declarative logger configuration, deserialisable from TOML via `serde`,
+/// mirroring Dropshot's logging config block. Lets applications configure the
+/// logger from a file instead of calling the individual setters.
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+pub struct ConfigLogging {
+    /// Where records are sent.
+    pub mode: ConfigMode,
+    /// The level directive token, e.g. `"warn"` or `"trace"`.
+    pub level: String,
+    /// The log file path; required (and only used) when `mode` is `File`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// How to treat a pre-existing log file; defaults to `Append`.
+    #[serde(default)]
+    pub if_exists: IfExists,
+}
+
+/// Applies a `ConfigLogging` to the global logger state.
+///
+/// The `level` token sets the global `LogLevel`. For `ConfigMode::File` the
+/// file at `path` is opened according to `if_exists` — `Truncate` clears an
+/// existing file, `Fail` surfaces an error if the file already exists, and
+/// `Append` keeps appending — after which it becomes the default log path.
+/// `ConfigMode::StderrTerminal` clears the default path so records go only to
+/// the console.
+pub fn init_from_config(cfg: &ConfigLogging) -> Result<(), std::io::Error> {
+    if let Some(level) = level_from_token(&cfg.level) {
+        *LOG_LEVEL.lock().unwrap() = level;
+    }
+
+    match cfg.mode {
+        ConfigMode::StderrTerminal => {
+            set_path(String::new());
+        }
+        ConfigMode::File => {
+            let path = cfg.path.clone().ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "ConfigMode::File requires a `path`",
+                )
+            })?;
+
+            match cfg.if_exists {
+                IfExists::Append => {
+                    OpenOptions::new().append(true).create(true).open(&path)?;
+                }
+                IfExists::Truncate => {
+                    OpenOptions::new()
+                        .write(true)
+                        .create(true)
+                        .truncate(true)
+                        .open(&path)?;
+                }
+                IfExists::Fail => {
+                    OpenOptions::new().write(true).create_new(true).open(&path)?;
+                }
+            }
+
+            set_path(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Configures the logger from an environment variable, `env_logger`-style.
+///
+/// The variable's value is a comma-separated directive: a bare level token
+/// (`warn`, `debug`, ...) sets the global default, while `path=level` entries
+/// set per-module overrides, e.g. `SW_LOG=warn,net=debug,net::http=trace`. When
+/// the variable is unset or empty the current configuration is left untouched.
+///
+/// `var` -> the name of the environment variable to read.
+pub fn init_from_env(var: &str) {
+    let directive = match std::env::var(var) {
+        Ok(value) if !value.is_empty() => value,
+        _ => return,
+    };
+
+    let (global, filters) = parse_directive(&directive);
+    if let Some(global) = global {
+        *LOG_LEVEL.lock().unwrap() = global;
+    }
+    *FILTERS.lock().unwrap() = filters;
+}
+
+/// Decides whether a record of type `t` passes the given `level` threshold.
+/// This is the single source of truth shared by `log`, the logging macros and
+/// `log_enabled!`.
+fn is_enabled(level: &LogLevel, t: &LogType) -> bool {
+    match level {
+        LogLevel::Critical => *t == LogType::Critical,
+        LogLevel::ErrorsOnly => matches!(t, LogType::Error | LogType::Critical),
+        LogLevel::Default => matches!(
+            t,
+            LogType::Warning | LogType::Error | LogType::Critical
+        ),
+        LogLevel::Info => *t != LogType::Trace && *t != LogType::Debug,
+        LogLevel::Debug => *t != LogType::Trace && *t != LogType::Info,
+        LogLevel::Verbose => true,
+    }
+}
+
+/// Returns whether a record of type `t` would be logged under the current
+/// configuration for the default (crate) target. Use this to gate expensive
+/// computation that only feeds a log message.
+pub fn level_enabled(t: &LogType) -> bool {
+    level_enabled_target(t, env!("CARGO_PKG_NAME"))
+}
+
+/// As `level_enabled`, but evaluated against the filter that applies to an
+/// explicit `target` (module path). Used by the logging macros.
+pub fn level_enabled_target(t: &LogType, target: &str) -> bool {
+    is_enabled(&effective_level(target), t)
+}
+
+/// Resolves the `LogLevel` that applies to `target`, honouring per-module
+/// overrides with longest-prefix-wins precedence and falling back to the global
+/// `LOG_LEVEL` when no override matches.
+fn effective_level(target: &str) -> LogLevel {
+    let filters = FILTERS.lock().unwrap();
+    let mut best: Option<(&str, &LogLevel)> = None;
+    for (prefix, level) in filters.iter() {
+        let matches = target == prefix
+            || target.starts_with(prefix) && target[prefix.len()..].starts_with("::");
+        if matches && best.is_none_or(|(p, _)| prefix.len() > p.len()) {
+            best = Some((prefix, level));
+        }
+    }
+
+    match best {
+        Some((_, level)) => level.clone(),
+        None => LOG_LEVEL.lock().unwrap().clone(),
+    }
+}
+
 /// Sets the default path the logger uses to write to.
 /// If left as an empty `String` (or set as one), the logger won't write to a file; only to the `stdout` and the `stderr`.
 pub fn set_path(path: String) {
@@ -68,50 +601,221 @@ pub fn set_path(path: String) {
 /// write to the default path set with `set_path`. A custom path can be specified like so:
 /// `Some("/the/path/here")`.
 pub fn log(message: &str, t: LogType, p: Option<&str>) -> Result<String, std::io::Error> {
+    log_target(message, t, p, &[], env!("CARGO_PKG_NAME"))
+}
+
+/// Like `log`, but also attaches structured key/value pairs to the record.
+///
+/// In `LogFormat::Text` mode the pairs are appended as ` key=value` after the
+/// message; in `LogFormat::Json` mode they become additional fields on the
+/// emitted JSON object. The level-filtering behaviour is identical to `log`.
+///
+/// `fields` -> the structured fields to attach, e.g. `&[("req_id", "42")]`.
+pub fn log_kv(
+    message: &str,
+    t: LogType,
+    p: Option<&str>,
+    fields: &[(&str, &str)],
+) -> Result<String, std::io::Error> {
+    log_target(message, t, p, fields, env!("CARGO_PKG_NAME"))
+}
+
+/// Like `log_kv`, but logged under an explicit `target` (module path) so that
+/// per-module filters configured via `init_from_env` can override the global
+/// level for this record.
+///
+/// `target` -> the module path this record belongs to, e.g. `"net::http"`.
+pub fn log_target(
+    message: &str,
+    t: LogType,
+    p: Option<&str>,
+    fields: &[(&str, &str)],
+    target: &str,
+) -> Result<String, std::io::Error> {
     let default_path = LOG_PATH.lock().unwrap().clone();
     let path = p.unwrap_or(&default_path);
 
     let timestamp = Local::now();
-    let formatted_timestamp = timestamp.format("%Y-%m-%d %H:%M:%S");
 
-    let formatted_message = format!(
-        "[{log_type}] {time} -> {message}",
-        log_type = t,
-        time = formatted_timestamp
-    );
-
-    let level = LOG_LEVEL.lock().unwrap().clone();
+    if !level_enabled_target(&t, target) {
+        return Ok("".to_string());
+    }
 
-    match level {
-        LogLevel::ErrorsOnly => {
-            if t != LogType::Error {
-                return Ok("".to_string());
+    let format = LOG_FORMAT.lock().unwrap().clone();
+    let formatted_message = match format {
+        LogFormat::Text => {
+            let formatted_timestamp = timestamp.format("%Y-%m-%d %H:%M:%S");
+            let mut line = format!(
+                "[{log_type}] {time} -> {message}",
+                log_type = t,
+                time = formatted_timestamp
+            );
+            for (key, value) in fields {
+                line.push_str(&format!(" {}={}", key, value));
             }
+            line
         }
-        LogLevel::Default => {
-            if t == LogType::Debug || t == LogType::Info {
-                return Ok("".to_string());
+        LogFormat::Json => {
+            let mut obj = format!(
+                "{{\"time\":\"{time}\",\"level\":{level},\"msg\":\"{msg}\",\"hostname\":\"{hostname}\",\"pid\":{pid}",
+                time = timestamp.to_rfc3339_opts(SecondsFormat::Secs, false),
+                level = t.severity(),
+                msg = json_escape(message),
+                hostname = json_escape(&hostname()),
+                pid = std::process::id(),
+            );
+            for (key, value) in fields {
+                obj.push_str(&format!(
+                    ",\"{}\":\"{}\"",
+                    json_escape(key),
+                    json_escape(value)
+                ));
             }
+            obj.push('}');
+            obj
         }
-        LogLevel::Debug => {
-            if t == LogType::Info {
-                return Ok("".to_string());
-            }
+    };
+
+    let is_error = t == LogType::Error;
+
+    // The console may get a coloured variant; the file always receives the
+    // plain, escape-free string so log files stay parseable.
+    let console_message = if matches!(format, LogFormat::Text) && should_color(is_error) {
+        colorize(&formatted_message, &t)
+    } else {
+        formatted_message.clone()
+    };
+
+    if let Some(tx) = ASYNC_TX.lock().unwrap().as_ref() {
+        let record = LogMessage::Record {
+            console: console_message,
+            file: formatted_message.clone(),
+            path: path.to_string(),
+            is_error,
+        };
+        if tx.try_send(record).is_err() {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
         }
-        _ => {}
+    } else {
+        emit(&console_message, &formatted_message, path, is_error)?;
     }
 
-    match t {
-        LogType::Error => eprintln!("{}", formatted_message),
-        _ => println!("{}", formatted_message),
+    Ok(formatted_message)
+}
+
+/// Writes a rendered record to the console (`console`) and, if `path` is
+/// non-empty, appends the plain `file` variant to the file at `path`. Shared by
+/// the synchronous path and the background writer thread so both behave
+/// identically.
+fn emit(console: &str, file: &str, path: &str, is_error: bool) -> Result<(), std::io::Error> {
+    if is_error {
+        eprintln!("{}", console);
+    } else {
+        println!("{}", console);
     }
 
-    if path != String::new() {
-        let mut file = OpenOptions::new().append(true).create(true).open(&path)?;
-        writeln!(file, "{}", &formatted_message)?;
+    if !path.is_empty() {
+        // Serialize the check-rotate-open-write sequence so that two threads
+        // logging to the same path cannot both decide to rotate and then race
+        // on the rename (the second would hit `ENOENT` and drop its message).
+        let _guard = FILE_IO.lock().unwrap();
+        maybe_rotate(path)?;
+        let mut handle = OpenOptions::new().append(true).create(true).open(path)?;
+        writeln!(handle, "{}", file)?;
     }
 
-    Ok(formatted_message)
+    Ok(())
+}
+
+/// A unit of work handed to the background writer thread.
+enum LogMessage {
+    /// A rendered record to emit: `console` is the (possibly coloured) console
+    /// variant, `file` the plain variant written to disk.
+    Record {
+        console: String,
+        file: String,
+        path: String,
+        is_error: bool,
+    },
+    /// Acknowledge once all prior records have been drained.
+    Flush(Sender<()>),
+    /// Drain remaining records and stop the thread.
+    Shutdown,
+}
+
+/// Enables non-blocking logging.
+///
+/// Spawns a dedicated writer thread that owns the console/file I/O; subsequent
+/// `log`/`log_kv` calls do their (cheap) level-filtering and formatting inline
+/// and then push the finished record over a bounded channel instead of writing
+/// on the caller's thread. `capacity` bounds the channel so a slow disk cannot
+/// grow memory without limit — when the channel is full, records are dropped
+/// and counted, and the count is flushed as a diagnostic once the writer
+/// catches up.
+///
+/// Call `flush` to wait for buffered records to drain, or `shutdown` at program
+/// exit to drain and join the thread so no records are lost. Until `init_async`
+/// is called the logger stays fully synchronous, so existing users are
+/// unaffected.
+///
+/// `capacity` -> the maximum number of buffered records before new ones are dropped.
+pub fn init_async(capacity: usize) {
+    let (tx, rx) = sync_channel::<LogMessage>(capacity);
+
+    let handle = std::thread::spawn(move || {
+        for message in rx {
+            match message {
+                LogMessage::Record {
+                    console,
+                    file,
+                    path,
+                    is_error,
+                } => {
+                    let dropped = DROPPED.swap(0, Ordering::Relaxed);
+                    if dropped > 0 {
+                        let notice = format!(
+                            "[WARNING] {} -> {} log message(s) dropped due to a full async channel",
+                            Local::now().format("%Y-%m-%d %H:%M:%S"),
+                            dropped
+                        );
+                        let _ = emit(&notice, &notice, &path, true);
+                    }
+                    let _ = emit(&console, &file, &path, is_error);
+                }
+                LogMessage::Flush(ack) => {
+                    let _ = ack.send(());
+                }
+                LogMessage::Shutdown => break,
+            }
+        }
+    });
+
+    *ASYNC_TX.lock().unwrap() = Some(tx);
+    *ASYNC_THREAD.lock().unwrap() = Some(handle);
+}
+
+/// Blocks until every record buffered before this call has been written by the
+/// background writer thread. A no-op when async mode is not active.
+pub fn flush() {
+    let tx = ASYNC_TX.lock().unwrap().clone();
+    if let Some(tx) = tx {
+        let (ack_tx, ack_rx) = std::sync::mpsc::channel();
+        if tx.send(LogMessage::Flush(ack_tx)).is_ok() {
+            let _ = ack_rx.recv();
+        }
+    }
+}
+
+/// Drains any buffered records, stops the background writer thread and reverts
+/// the logger to synchronous mode. Safe to call when async mode is not active.
+pub fn shutdown() {
+    let tx = ASYNC_TX.lock().unwrap().take();
+    if let Some(tx) = tx {
+        let _ = tx.send(LogMessage::Shutdown);
+    }
+    if let Some(handle) = ASYNC_THREAD.lock().unwrap().take() {
+        let _ = handle.join();
+    }
 }
 
 #[cfg(test)]
@@ -282,6 +986,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn json_format_emits_single_line_object() {
+        set_level(LogLevel::Verbose);
+        set_format(LogFormat::Json);
+
+        let logged_message = log("json test", LogType::Error, None).unwrap();
+        set_format(LogFormat::Text);
+
+        assert!(logged_message.starts_with('{'));
+        assert!(logged_message.ends_with('}'));
+        assert!(logged_message.contains("\"msg\":\"json test\""));
+        assert!(logged_message.contains("\"level\":50"));
+        assert!(logged_message.contains("\"pid\":"));
+    }
+
+    #[test]
+    fn text_kv_appends_key_value_pairs() {
+        set_level(LogLevel::Verbose);
+        set_format(LogFormat::Text);
+
+        let logged_message =
+            log_kv("kv test", LogType::Warning, None, &[("req_id", "42")]).unwrap();
+        assert!(logged_message.ends_with("-> kv test req_id=42"));
+    }
+
+    #[test]
+    fn size_rotation_renames_active_file() {
+        set_level(LogLevel::Verbose);
+        set_format(LogFormat::Text);
+        let path = "/tmp/sw-logger-rs-rotate-test.log";
+        let archive = format!("{}.1", path);
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(&archive);
+
+        set_rotation(RotationPolicy::Size(1));
+        // First write creates the file; the second sees it over the 1-byte
+        // limit and rotates it to `path.1` before writing afresh.
+        log("first", LogType::Error, Some(path)).unwrap();
+        log("second", LogType::Error, Some(path)).unwrap();
+        set_rotation(RotationPolicy::None);
+
+        assert!(std::path::Path::new(&archive).exists());
+        assert!(fs::read_to_string(&archive).unwrap().contains("first"));
+
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(&archive);
+    }
+
+    #[test]
+    fn critical_is_logged_even_when_errors_only() {
+        *FILTERS.lock().unwrap() = Vec::new();
+        set_level(LogLevel::ErrorsOnly);
+        assert!(level_enabled(&LogType::Critical));
+        assert!(level_enabled(&LogType::Error));
+        assert!(!level_enabled(&LogType::Warning));
+    }
+
+    #[test]
+    fn config_parses_from_toml() {
+        let cfg: ConfigLogging = toml::from_str(
+            "mode = \"file\"\nlevel = \"trace\"\npath = \"/tmp/sw-logger-cfg.log\"\nif_exists = \"truncate\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.mode, ConfigMode::File);
+        assert_eq!(cfg.if_exists, IfExists::Truncate);
+        assert_eq!(cfg.path.as_deref(), Some("/tmp/sw-logger-cfg.log"));
+    }
+
+    #[test]
+    fn config_if_exists_fail_errors_when_file_present() {
+        let path = "/tmp/sw-logger-rs-fail-test.log";
+        let _ = fs::write(path, "existing");
+
+        let cfg = ConfigLogging {
+            mode: ConfigMode::File,
+            level: "warn".to_string(),
+            path: Some(path.to_string()),
+            if_exists: IfExists::Fail,
+        };
+        assert!(init_from_config(&cfg).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn colorize_wraps_only_the_level_signifier() {
+        let colored = colorize("[ERROR] 2024-02-21 12:00:00 -> boom", &LogType::Error);
+        assert_eq!(
+            colored,
+            "\x1b[31m[ERROR]\x1b[0m 2024-02-21 12:00:00 -> boom"
+        );
+    }
+
+    #[test]
+    fn color_mode_never_disables_coloring() {
+        set_color(ColorMode::Never);
+        assert!(!should_color(true));
+        set_color(ColorMode::Always);
+        assert!(should_color(false));
+        set_color(ColorMode::Auto);
+    }
+
+    #[test]
+    fn level_enabled_tracks_global_level() {
+        *FILTERS.lock().unwrap() = Vec::new();
+        set_level(LogLevel::Default);
+        assert!(!level_enabled(&LogType::Info));
+        assert!(!level_enabled(&LogType::Debug));
+        assert!(level_enabled(&LogType::Warning));
+        assert!(level_enabled(&LogType::Error));
+
+        set_level(LogLevel::Verbose);
+        assert!(level_enabled(&LogType::Info));
+    }
+
+    #[test]
+    fn per_module_filter_overrides_global() {
+        set_level(LogLevel::ErrorsOnly);
+        *FILTERS.lock().unwrap() = vec![("net::http".to_string(), LogLevel::Verbose)];
+
+        assert_eq!(effective_level("net::http::client"), LogLevel::Verbose);
+        assert_eq!(effective_level("net"), LogLevel::ErrorsOnly);
+        assert_eq!(effective_level("network"), LogLevel::ErrorsOnly);
+
+        *FILTERS.lock().unwrap() = Vec::new();
+    }
+
+    #[test]
+    fn parse_directive_sets_global_and_overrides() {
+        let (global, filters) = parse_directive("warn,net=debug,net::http=trace");
+        assert_eq!(global, Some(LogLevel::Default));
+        assert_eq!(
+            filters,
+            vec![
+                ("net".to_string(), LogLevel::Debug),
+                ("net::http".to_string(), LogLevel::Verbose),
+            ]
+        );
+    }
+
+    #[test]
+    fn async_mode_writes_after_flush() {
+        set_level(LogLevel::Verbose);
+        set_format(LogFormat::Text);
+        let path = "/tmp/sw-logger-rs-async-test.log";
+        let _ = fs::remove_file(path);
+
+        init_async(8);
+        let logged_message = log("async test", LogType::Error, Some(path)).unwrap();
+        flush();
+        shutdown();
+
+        assert!(
+            check_string_in_file(path, &logged_message),
+            "Did not find async test string in log file."
+        );
+    }
+
     #[test]
     fn log_level_default_does_not_log_info_debug() {
         set_path(String::from(